@@ -8,15 +8,22 @@ use crate::utils::align;
 use bytes::{Buf, BufMut, Bytes, BytesMut};
 use goblin::container::Endian;
 use unicorn_engine::unicorn_const::{uc_error, Arch, Mode};
-use unicorn_engine::{RegisterMIPS, Unicorn};
+use unicorn_engine::{RegisterARM64, RegisterMIPS, RegisterRISCV, Unicorn};
 
 pub trait ArchT {
     fn endian(&self) -> Endian;
     fn pointer_size(&self) -> PointerSizeT;
     fn pc_reg_id(&self) -> i32;
     fn sp_reg_id(&self) -> i32;
+    fn fp_reg_id(&self) -> i32;
     fn arch(&self) -> Arch;
     fn mode(&self) -> Mode;
+    fn syscall_number_reg_id(&self) -> i32;
+    fn syscall_arg_reg_ids(&self) -> Vec<i32>;
+    /// The unicorn `intno` raised by this architecture's syscall-trapping
+    /// instruction (MIPS `syscall`, ARM64 `svc`, RISC-V `ecall`), so the
+    /// `INTR` hook can tell a syscall trap apart from unrelated interrupts.
+    fn syscall_intno(&self) -> u32;
 }
 
 #[derive(Copy, Eq, PartialEq, Debug, Clone)]
@@ -78,6 +85,27 @@ impl ArchT for ArchMIPS {
     fn sp_reg_id(&self) -> i32 {
         RegisterMIPS::SP as i32
     }
+
+    fn fp_reg_id(&self) -> i32 {
+        RegisterMIPS::FP as i32
+    }
+
+    fn syscall_number_reg_id(&self) -> i32 {
+        RegisterMIPS::V0 as i32
+    }
+
+    fn syscall_arg_reg_ids(&self) -> Vec<i32> {
+        vec![
+            RegisterMIPS::A0 as i32,
+            RegisterMIPS::A1 as i32,
+            RegisterMIPS::A2 as i32,
+            RegisterMIPS::A3 as i32,
+        ]
+    }
+
+    fn syscall_intno(&self) -> u32 {
+        17
+    }
 }
 
 #[derive(Debug)]
@@ -92,12 +120,14 @@ struct MipsCC {
 
 impl MipsCC {
     const RET_REG: i32 = RegisterMIPS::V0 as i32;
-    const ARG_REGS: Vec<i32> = vec![
-        RegisterMIPS::A0 as i32,
-        RegisterMIPS::A1 as i32,
-        RegisterMIPS::A2 as i32,
-        RegisterMIPS::A3 as i32,
-    ];
+    fn arg_regs() -> Vec<i32> {
+        vec![
+            RegisterMIPS::A0 as i32,
+            RegisterMIPS::A1 as i32,
+            RegisterMIPS::A2 as i32,
+            RegisterMIPS::A3 as i32,
+        ]
+    }
     const ARG_ON_STACK: u8 = 12;
     const SHADOW: u8 = 4;
     const RET_ADDR_ON_STACK: bool = false;
@@ -119,6 +149,10 @@ impl ArchT for MIPS {
         self.arch_info.sp_reg_id()
     }
 
+    fn fp_reg_id(&self) -> i32 {
+        self.arch_info.fp_reg_id()
+    }
+
     fn arch(&self) -> Arch {
         self.arch_info.arch()
     }
@@ -126,6 +160,18 @@ impl ArchT for MIPS {
     fn mode(&self) -> Mode {
         self.arch_info.mode()
     }
+
+    fn syscall_number_reg_id(&self) -> i32 {
+        self.arch_info.syscall_number_reg_id()
+    }
+
+    fn syscall_arg_reg_ids(&self) -> Vec<i32> {
+        self.arch_info.syscall_arg_reg_ids()
+    }
+
+    fn syscall_intno(&self) -> u32 {
+        self.arch_info.syscall_intno()
+    }
 }
 
 impl MIPS {
@@ -135,7 +181,7 @@ impl MIPS {
             cc: MipsCC {
                 inner: CallingConventionCommon::new(
                     MipsCC::RET_REG,
-                    MipsCC::ARG_REGS,
+                    MipsCC::arg_regs(),
                     MipsCC::ARG_ON_STACK,
                     MipsCC::SHADOW as u64,
                     MipsCC::RET_ADDR_ON_STACK,
@@ -154,6 +200,443 @@ impl MIPS {
     }
 }
 
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct ArchRISCV64;
+
+impl Default for ArchRISCV64 {
+    fn default() -> Self {
+        Self
+    }
+}
+
+impl ArchT for ArchRISCV64 {
+    fn endian(&self) -> Endian {
+        Endian::Little
+    }
+    fn arch(&self) -> Arch {
+        Arch::RISCV
+    }
+    fn mode(&self) -> Mode {
+        Mode::MODE_64
+    }
+    fn pointer_size(&self) -> u8 {
+        8
+    }
+
+    fn pc_reg_id(&self) -> i32 {
+        RegisterRISCV::PC as i32
+    }
+
+    fn sp_reg_id(&self) -> i32 {
+        RegisterRISCV::SP as i32
+    }
+
+    fn fp_reg_id(&self) -> i32 {
+        RegisterRISCV::FP as i32
+    }
+
+    fn syscall_number_reg_id(&self) -> i32 {
+        RegisterRISCV::A7 as i32
+    }
+
+    fn syscall_arg_reg_ids(&self) -> Vec<i32> {
+        vec![
+            RegisterRISCV::A0 as i32,
+            RegisterRISCV::A1 as i32,
+            RegisterRISCV::A2 as i32,
+            RegisterRISCV::A3 as i32,
+            RegisterRISCV::A4 as i32,
+            RegisterRISCV::A5 as i32,
+        ]
+    }
+
+    fn syscall_intno(&self) -> u32 {
+        // RISC-V "ecall" from U-mode: exception cause 8.
+        8
+    }
+}
+
+#[derive(Debug)]
+pub struct RISCV64 {
+    pub(crate) arch_info: ArchRISCV64,
+    pub(crate) cc: RiscvCC,
+}
+#[derive(Clone, Debug)]
+struct RiscvCC {
+    inner: CallingConventionCommon,
+}
+
+impl RiscvCC {
+    const RET_REG: i32 = RegisterRISCV::A0 as i32;
+    fn arg_regs() -> Vec<i32> {
+        vec![
+            RegisterRISCV::A0 as i32,
+            RegisterRISCV::A1 as i32,
+            RegisterRISCV::A2 as i32,
+            RegisterRISCV::A3 as i32,
+            RegisterRISCV::A4 as i32,
+            RegisterRISCV::A5 as i32,
+            RegisterRISCV::A6 as i32,
+            RegisterRISCV::A7 as i32,
+        ]
+    }
+    const ARG_ON_STACK: u8 = 8;
+    const SHADOW: u8 = 0;
+    const RET_ADDR_ON_STACK: bool = false;
+}
+impl ArchT for RISCV64 {
+    fn endian(&self) -> Endian {
+        self.arch_info.endian()
+    }
+
+    fn pointer_size(&self) -> PointerSizeT {
+        self.arch_info.pointer_size()
+    }
+
+    fn pc_reg_id(&self) -> i32 {
+        self.arch_info.pc_reg_id()
+    }
+
+    fn sp_reg_id(&self) -> i32 {
+        self.arch_info.sp_reg_id()
+    }
+
+    fn fp_reg_id(&self) -> i32 {
+        self.arch_info.fp_reg_id()
+    }
+
+    fn arch(&self) -> Arch {
+        self.arch_info.arch()
+    }
+
+    fn mode(&self) -> Mode {
+        self.arch_info.mode()
+    }
+
+    fn syscall_number_reg_id(&self) -> i32 {
+        self.arch_info.syscall_number_reg_id()
+    }
+
+    fn syscall_arg_reg_ids(&self) -> Vec<i32> {
+        self.arch_info.syscall_arg_reg_ids()
+    }
+
+    fn syscall_intno(&self) -> u32 {
+        self.arch_info.syscall_intno()
+    }
+}
+
+impl RISCV64 {
+    pub fn new(arch: ArchRISCV64) -> Self {
+        Self {
+            arch_info: arch,
+            cc: RiscvCC {
+                inner: CallingConventionCommon::new(
+                    RiscvCC::RET_REG,
+                    RiscvCC::arg_regs(),
+                    RiscvCC::ARG_ON_STACK,
+                    RiscvCC::SHADOW as u64,
+                    RiscvCC::RET_ADDR_ON_STACK,
+                    arch.pointer_size(),
+                ),
+            },
+        }
+    }
+
+    pub fn pointersize(&self) -> u8 {
+        self.arch_info.pointer_size()
+    }
+    pub fn endian(&self) -> Endian {
+        self.arch_info.endian()
+    }
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct ArchARM64;
+
+impl Default for ArchARM64 {
+    fn default() -> Self {
+        Self
+    }
+}
+
+impl ArchT for ArchARM64 {
+    fn endian(&self) -> Endian {
+        Endian::Little
+    }
+    fn arch(&self) -> Arch {
+        Arch::ARM64
+    }
+    fn mode(&self) -> Mode {
+        Mode::MODE_LITTLE_ENDIAN
+    }
+    fn pointer_size(&self) -> u8 {
+        8
+    }
+
+    fn pc_reg_id(&self) -> i32 {
+        RegisterARM64::PC as i32
+    }
+
+    fn sp_reg_id(&self) -> i32 {
+        RegisterARM64::SP as i32
+    }
+
+    fn fp_reg_id(&self) -> i32 {
+        RegisterARM64::FP as i32
+    }
+
+    fn syscall_number_reg_id(&self) -> i32 {
+        RegisterARM64::X8 as i32
+    }
+
+    fn syscall_arg_reg_ids(&self) -> Vec<i32> {
+        vec![
+            RegisterARM64::X0 as i32,
+            RegisterARM64::X1 as i32,
+            RegisterARM64::X2 as i32,
+            RegisterARM64::X3 as i32,
+            RegisterARM64::X4 as i32,
+            RegisterARM64::X5 as i32,
+        ]
+    }
+
+    fn syscall_intno(&self) -> u32 {
+        // AArch64 "svc": EXCP_SWI.
+        2
+    }
+}
+
+#[derive(Debug)]
+pub struct ARM64 {
+    pub(crate) arch_info: ArchARM64,
+    pub(crate) cc: Arm64CC,
+}
+#[derive(Clone, Debug)]
+struct Arm64CC {
+    inner: CallingConventionCommon,
+}
+
+impl Arm64CC {
+    const RET_REG: i32 = RegisterARM64::X0 as i32;
+    fn arg_regs() -> Vec<i32> {
+        vec![
+            RegisterARM64::X0 as i32,
+            RegisterARM64::X1 as i32,
+            RegisterARM64::X2 as i32,
+            RegisterARM64::X3 as i32,
+            RegisterARM64::X4 as i32,
+            RegisterARM64::X5 as i32,
+            RegisterARM64::X6 as i32,
+            RegisterARM64::X7 as i32,
+        ]
+    }
+    const ARG_ON_STACK: u8 = 8;
+    const SHADOW: u8 = 0;
+    const RET_ADDR_ON_STACK: bool = false;
+}
+impl ArchT for ARM64 {
+    fn endian(&self) -> Endian {
+        self.arch_info.endian()
+    }
+
+    fn pointer_size(&self) -> PointerSizeT {
+        self.arch_info.pointer_size()
+    }
+
+    fn pc_reg_id(&self) -> i32 {
+        self.arch_info.pc_reg_id()
+    }
+
+    fn sp_reg_id(&self) -> i32 {
+        self.arch_info.sp_reg_id()
+    }
+
+    fn fp_reg_id(&self) -> i32 {
+        self.arch_info.fp_reg_id()
+    }
+
+    fn arch(&self) -> Arch {
+        self.arch_info.arch()
+    }
+
+    fn mode(&self) -> Mode {
+        self.arch_info.mode()
+    }
+
+    fn syscall_number_reg_id(&self) -> i32 {
+        self.arch_info.syscall_number_reg_id()
+    }
+
+    fn syscall_arg_reg_ids(&self) -> Vec<i32> {
+        self.arch_info.syscall_arg_reg_ids()
+    }
+
+    fn syscall_intno(&self) -> u32 {
+        self.arch_info.syscall_intno()
+    }
+}
+
+impl ARM64 {
+    pub fn new(arch: ArchARM64) -> Self {
+        Self {
+            arch_info: arch,
+            cc: Arm64CC {
+                inner: CallingConventionCommon::new(
+                    Arm64CC::RET_REG,
+                    Arm64CC::arg_regs(),
+                    Arm64CC::ARG_ON_STACK,
+                    Arm64CC::SHADOW as u64,
+                    Arm64CC::RET_ADDR_ON_STACK,
+                    arch.pointer_size(),
+                ),
+            },
+        }
+    }
+
+    pub fn pointersize(&self) -> u8 {
+        self.arch_info.pointer_size()
+    }
+    pub fn endian(&self) -> Endian {
+        self.arch_info.endian()
+    }
+}
+
+/// Reads one pointer-sized word from `address`, respecting `core.endian()`
+/// and `core.pointer_size()`.
+fn read_word<'a, A: ArchT>(core: &Core<'a, A>, address: u64) -> crate::errors::Result<u64>
+where
+    Core<'a, A>: Memory,
+{
+    let psize = core.pointer_size() as usize;
+    let raw = core.mem_read_as_vec(address, psize)?;
+    let mut buf = Bytes::copy_from_slice(&raw);
+    Ok(match (core.endian(), psize) {
+        (Endian::Little, 4) => buf.get_u32_le() as u64,
+        (Endian::Big, 4) => buf.get_u32() as u64,
+        (Endian::Little, 8) => buf.get_u64_le(),
+        (Endian::Big, 8) => buf.get_u64(),
+        _ => return Err(EmulatorError::InvalidArg("unsupported pointer size".into())),
+    })
+}
+
+/// Walks `nslots` saved frame/return-address pairs off the stack starting
+/// from `ArchT::fp_reg_id()`, generic over `ArchT` so the same logic serves
+/// MIPS, ARM64 and RISC-V alike.
+///
+/// Each frame is a `(saved_fp, return_address)` pair, pointer-sized words
+/// starting at the current frame pointer; walking one frame moves to
+/// `saved_fp` and records `return_address` as the resolved caller PC. On
+/// return, SP is restored to the final frame reached. `current_ra` is
+/// returned unchanged when `nslots` is 0, matching the pre-unwinder
+/// behaviour of just reading the link/return-address register.
+fn unwind_frames<'a, A: ArchT>(
+    core: &mut Core<'a, A>,
+    nslots: u64,
+    current_ra: u64,
+) -> crate::errors::Result<u64>
+where
+    Core<'a, A>: Memory,
+{
+    if nslots == 0 {
+        return Ok(current_ra);
+    }
+    let psize = core.pointer_size() as u64;
+    let frame = core.reg_read(core.fp_reg_id())?;
+    let (final_frame, ret_addr) =
+        walk_frames(nslots, psize, frame, current_ra, |addr| read_word(core, addr))?;
+    core.reg_write(core.sp_reg_id(), final_frame)?;
+    Ok(ret_addr)
+}
+
+/// The pure frame-walking step behind [`unwind_frames`], split out so it can
+/// be unit tested without a live `Core`/unicorn engine: given a starting
+/// frame pointer and a way to read a word at an address, walks `nslots`
+/// `(saved_fp, return_address)` pairs and returns the final frame pointer
+/// and resolved return address.
+fn walk_frames(
+    nslots: u64,
+    psize: u64,
+    mut frame: u64,
+    current_ra: u64,
+    mut read_word: impl FnMut(u64) -> crate::errors::Result<u64>,
+) -> crate::errors::Result<(u64, u64)> {
+    let mut ret_addr = current_ra;
+    for _ in 0..nslots {
+        if frame == 0 || frame % psize != 0 {
+            return Err(EmulatorError::InvalidArg(format!(
+                "misaligned or null frame pointer: {:#x}",
+                frame
+            )));
+        }
+        let saved_fp = read_word(frame)?;
+        let saved_ra = read_word(frame + psize)?;
+        ret_addr = saved_ra;
+        frame = saved_fp;
+    }
+    Ok((frame, ret_addr))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn fake_memory(words: &[(u64, u64)]) -> HashMap<u64, u64> {
+        words.iter().copied().collect()
+    }
+
+    fn read_from(mem: &HashMap<u64, u64>, addr: u64) -> crate::errors::Result<u64> {
+        mem.get(&addr).copied().ok_or_else(|| {
+            EmulatorError::InvalidArg(format!("unmapped test address: {:#x}", addr))
+        })
+    }
+
+    #[test]
+    fn unwind_zero_slots_returns_current_ra_unchanged() {
+        let mem = fake_memory(&[]);
+        let (frame, ra) = walk_frames(0, 8, 0x1000, 0xdead, |a| read_from(&mem, a)).unwrap();
+        assert_eq!(frame, 0x1000);
+        assert_eq!(ra, 0xdead);
+    }
+
+    #[test]
+    fn unwind_one_slot_reads_saved_fp_and_ra() {
+        // frame 0x1000 holds (saved_fp=0x2000, saved_ra=0xcafe) at [0x1000, 0x1008).
+        let mem = fake_memory(&[(0x1000, 0x2000), (0x1008, 0xcafe)]);
+        let (frame, ra) = walk_frames(1, 8, 0x1000, 0xdead, |a| read_from(&mem, a)).unwrap();
+        assert_eq!(frame, 0x2000);
+        assert_eq!(ra, 0xcafe);
+    }
+
+    #[test]
+    fn unwind_multiple_slots_chains_through_frames() {
+        let mem = fake_memory(&[
+            (0x1000, 0x2000),
+            (0x1008, 0xcafe1),
+            (0x2000, 0x3000),
+            (0x2008, 0xcafe2),
+        ]);
+        let (frame, ra) = walk_frames(2, 8, 0x1000, 0xdead, |a| read_from(&mem, a)).unwrap();
+        assert_eq!(frame, 0x3000);
+        assert_eq!(ra, 0xcafe2);
+    }
+
+    #[test]
+    fn unwind_rejects_null_frame_pointer() {
+        let mem = fake_memory(&[]);
+        let err = walk_frames(1, 8, 0, 0xdead, |a| read_from(&mem, a)).unwrap_err();
+        assert!(matches!(err, EmulatorError::InvalidArg(_)));
+    }
+
+    #[test]
+    fn unwind_rejects_misaligned_frame_pointer() {
+        let mem = fake_memory(&[]);
+        let err = walk_frames(1, 8, 0x1001, 0xdead, |a| read_from(&mem, a)).unwrap_err();
+        assert!(matches!(err, EmulatorError::InvalidArg(_)));
+    }
+}
+
 impl<'a, A: ArchT> ArchT for Core<'a, A> {
     fn endian(&self) -> Endian {
         self.get_data().arch_info.endian()
@@ -171,6 +654,10 @@ impl<'a, A: ArchT> ArchT for Core<'a, A> {
         self.get_data().arch_info.sp_reg_id()
     }
 
+    fn fp_reg_id(&self) -> i32 {
+        self.get_data().arch_info.fp_reg_id()
+    }
+
     fn arch(&self) -> Arch {
         self.get_data().arch_info.arch()
     }
@@ -178,6 +665,18 @@ impl<'a, A: ArchT> ArchT for Core<'a, A> {
     fn mode(&self) -> Mode {
         self.get_data().arch_info.mode()
     }
+
+    fn syscall_number_reg_id(&self) -> i32 {
+        self.get_data().arch_info.syscall_number_reg_id()
+    }
+
+    fn syscall_arg_reg_ids(&self) -> Vec<i32> {
+        self.get_data().arch_info.syscall_arg_reg_ids()
+    }
+
+    fn syscall_intno(&self) -> u32 {
+        self.get_data().arch_info.syscall_intno()
+    }
 }
 
 impl<'a> CallingConvention for Core<'a, MIPS> {
@@ -221,7 +720,99 @@ impl<'a> CallingConvention for Core<'a, MIPS> {
     }
 
     fn unwind(&mut self, nslots: u64) -> Result<u64, EmulatorError> {
-        // TODO: stack frame unwinding?
-        Ok(self.reg_read(RegisterMIPS::RA)?)
+        let ra = self.reg_read(RegisterMIPS::RA)?;
+        unwind_frames(self, nslots, ra)
+    }
+}
+
+impl<'a> CallingConvention for Core<'a, RISCV64> {
+    #[inline]
+    fn get_num_slots(argbits: u64) -> u64 {
+        1
+    }
+
+    fn get_raw_param(&self, slot: u64, argbits: Option<u64>) -> crate::errors::Result<u64> {
+        let inner = self.get_data().arch_info.cc.inner.clone();
+        inner.get_ram_param(self, slot as u8, argbits)
+    }
+
+    fn set_raw_param(
+        &mut self,
+        slot: u64,
+        value: u64,
+        argbits: Option<u64>,
+    ) -> crate::errors::Result<()> {
+        let inner = self.get_data().arch_info.cc.inner.clone();
+        inner.set_raw_param(self, slot as u8, value, argbits)
+    }
+
+    fn get_return_value(&self) -> crate::errors::Result<u64> {
+        let inner = self.get_data().arch_info.cc.inner.clone();
+        inner.get_return_value(self)
+    }
+
+    fn set_return_value(&mut self, val: u64) -> crate::errors::Result<()> {
+        let inner = self.get_data().arch_info.cc.inner.clone();
+        inner.set_return_value(self, val)
+    }
+
+    fn set_return_address(&mut self, addr: u64) -> crate::errors::Result<()> {
+        unreachable!()
+    }
+
+    fn reserve(&mut self, nslots: u64) -> crate::errors::Result<()> {
+        let inner = self.get_data().arch_info.cc.inner.clone();
+        inner.reserve(self, nslots as usize)
+    }
+
+    fn unwind(&mut self, nslots: u64) -> Result<u64, EmulatorError> {
+        let ra = self.reg_read(RegisterRISCV::RA)?;
+        unwind_frames(self, nslots, ra)
+    }
+}
+
+impl<'a> CallingConvention for Core<'a, ARM64> {
+    #[inline]
+    fn get_num_slots(argbits: u64) -> u64 {
+        1
+    }
+
+    fn get_raw_param(&self, slot: u64, argbits: Option<u64>) -> crate::errors::Result<u64> {
+        let inner = self.get_data().arch_info.cc.inner.clone();
+        inner.get_ram_param(self, slot as u8, argbits)
+    }
+
+    fn set_raw_param(
+        &mut self,
+        slot: u64,
+        value: u64,
+        argbits: Option<u64>,
+    ) -> crate::errors::Result<()> {
+        let inner = self.get_data().arch_info.cc.inner.clone();
+        inner.set_raw_param(self, slot as u8, value, argbits)
+    }
+
+    fn get_return_value(&self) -> crate::errors::Result<u64> {
+        let inner = self.get_data().arch_info.cc.inner.clone();
+        inner.get_return_value(self)
+    }
+
+    fn set_return_value(&mut self, val: u64) -> crate::errors::Result<()> {
+        let inner = self.get_data().arch_info.cc.inner.clone();
+        inner.set_return_value(self, val)
+    }
+
+    fn set_return_address(&mut self, addr: u64) -> crate::errors::Result<()> {
+        unreachable!()
+    }
+
+    fn reserve(&mut self, nslots: u64) -> crate::errors::Result<()> {
+        let inner = self.get_data().arch_info.cc.inner.clone();
+        inner.reserve(self, nslots as usize)
+    }
+
+    fn unwind(&mut self, nslots: u64) -> Result<u64, EmulatorError> {
+        let ra = self.reg_read(RegisterARM64::LR)?;
+        unwind_frames(self, nslots, ra)
     }
 }
\ No newline at end of file
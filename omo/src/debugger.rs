@@ -0,0 +1,169 @@
+use crate::arch::ArchT;
+use crate::core::Core;
+use crate::errors::Result;
+use std::cell::{Cell, RefCell};
+use std::collections::HashSet;
+use std::rc::Rc;
+
+/// Exposes what the debugger needs from an emulated core: the current PC and
+/// a snapshot of register state, without tying it to a specific `ArchT`.
+pub trait Debuggable: ArchT {
+    fn current_pc(&mut self) -> Result<u64>;
+    fn dump_registers(&mut self) -> Result<Vec<(String, u64)>>;
+}
+
+impl<'a, A: ArchT> Debuggable for Core<'a, A> {
+    fn current_pc(&mut self) -> Result<u64> {
+        let id = self.pc_reg_id();
+        self.reg_read(id)
+    }
+
+    fn dump_registers(&mut self) -> Result<Vec<(String, u64)>> {
+        let pc = self.current_pc()?;
+        let sp = self.reg_read(self.sp_reg_id())?;
+        Ok(vec![("pc".into(), pc), ("sp".into(), sp)])
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Command {
+    Step,
+    Continue,
+    Break(u64),
+    Read(u64, usize),
+    Regs,
+    Dis,
+}
+
+/// Drives a `Core` step-by-step, stopping at registered breakpoints and
+/// accepting a small command language (`step`, `continue`, `break <addr>`,
+/// `read <addr> <len>`, `regs`, `dis`). An empty line repeats `last_command`.
+///
+/// The code hook is installed once, in `new()`, and reads `breakpoints`/
+/// `trace_only` live through shared state; `cont()` never installs another
+/// one, so repeated `continue`s don't stack up stale hooks.
+pub struct Debugger<'a, A: ArchT> {
+    core: Core<'a, A>,
+    breakpoints: Rc<RefCell<HashSet<u64>>>,
+    last_command: Option<Command>,
+    trace_only: Rc<Cell<bool>>,
+}
+
+impl<'a, A: ArchT + 'static> Debugger<'a, A>
+where
+    Core<'a, A>: Debuggable,
+{
+    pub fn new(mut core: Core<'a, A>) -> Result<Self> {
+        let breakpoints = Rc::new(RefCell::new(HashSet::new()));
+        let trace_only = Rc::new(Cell::new(false));
+
+        let hook_breakpoints = breakpoints.clone();
+        let hook_trace_only = trace_only.clone();
+        core.add_code_hook(move |core, address, _size| {
+            if hook_trace_only.get() {
+                log::trace!("{:#x}", address);
+                return;
+            }
+            if hook_breakpoints.borrow().contains(&address) {
+                let _ = core.emu_stop();
+            }
+        })?;
+
+        Ok(Self {
+            core,
+            breakpoints,
+            last_command: None,
+            trace_only,
+        })
+    }
+
+    pub fn set_trace_only(&mut self, enabled: bool) {
+        self.trace_only.set(enabled);
+    }
+
+    pub fn add_breakpoint(&mut self, addr: u64) {
+        self.breakpoints.borrow_mut().insert(addr);
+    }
+
+    pub fn remove_breakpoint(&mut self, addr: u64) {
+        self.breakpoints.borrow_mut().remove(&addr);
+    }
+
+    pub fn parse_command(&mut self, line: &str) -> Option<Command> {
+        let line = line.trim();
+        let command = if line.is_empty() {
+            self.last_command
+        } else {
+            Self::parse(line)
+        };
+        self.last_command = command;
+        command
+    }
+
+    fn parse(line: &str) -> Option<Command> {
+        let mut parts = line.split_whitespace();
+        match parts.next()? {
+            "step" | "s" => Some(Command::Step),
+            "continue" | "c" => Some(Command::Continue),
+            "break" | "b" => parts.next()?.parse().ok().map(Command::Break),
+            "read" | "x" => {
+                let addr = parts.next()?.parse().ok()?;
+                let len = parts.next()?.parse().ok()?;
+                Some(Command::Read(addr, len))
+            }
+            "regs" | "r" => Some(Command::Regs),
+            "dis" | "d" => Some(Command::Dis),
+            _ => None,
+        }
+    }
+
+    pub fn execute(&mut self, command: Command) -> Result<()> {
+        match command {
+            Command::Step => self.step(),
+            Command::Continue => self.cont(),
+            Command::Break(addr) => {
+                self.add_breakpoint(addr);
+                Ok(())
+            }
+            Command::Read(addr, len) => {
+                let data = self.core.mem_read_as_vec(addr, len)?;
+                log::info!("{:02x?}", data);
+                Ok(())
+            }
+            Command::Regs => {
+                for (name, value) in self.core.dump_registers()? {
+                    log::info!("{} = {:#x}", name, value);
+                }
+                Ok(())
+            }
+            Command::Dis => Ok(()),
+        }
+    }
+
+    pub fn step(&mut self) -> Result<()> {
+        if self.step_over_breakpoint()? {
+            return Ok(());
+        }
+        self.core.emu_start_count(1)
+    }
+
+    pub fn cont(&mut self) -> Result<()> {
+        self.step_over_breakpoint()?;
+        self.core.emu_start()
+    }
+
+    /// If PC currently sits on a breakpoint, the code hook would re-trigger
+    /// on it before a single instruction executes. Temporarily disarm that
+    /// one breakpoint, execute exactly the instruction it guards, then
+    /// re-arm it, so `step`/`continue` can actually advance past it.
+    /// Returns whether a breakpoint was stepped over.
+    fn step_over_breakpoint(&mut self) -> Result<bool> {
+        let pc = self.core.current_pc()?;
+        let hit = self.breakpoints.borrow_mut().remove(&pc);
+        if hit {
+            self.core.emu_start_count(1)?;
+            self.breakpoints.borrow_mut().insert(pc);
+        }
+        Ok(hit)
+    }
+}
@@ -0,0 +1,218 @@
+use crate::arch::ArchT;
+use crate::core::Core;
+use crate::errors::Result;
+use unicorn_engine::RegisterARM64;
+
+/// A named sub-register modeled as an (offset_bits, width_bits) window into
+/// a full-width physical register, e.g. x86 `al` is bits `[0, 8)` of `rax`,
+/// ARM64 `w0` is bits `[0, 32)` of `x0`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SubRegister {
+    pub full_reg_id: i32,
+    pub offset_bits: u32,
+    pub width_bits: u32,
+    pub signed: bool,
+}
+
+impl SubRegister {
+    pub const fn new(full_reg_id: i32, offset_bits: u32, width_bits: u32) -> Self {
+        Self {
+            full_reg_id,
+            offset_bits,
+            width_bits,
+            signed: false,
+        }
+    }
+
+    pub const fn signed(mut self) -> Self {
+        self.signed = true;
+        self
+    }
+
+    fn mask(&self) -> u64 {
+        if self.width_bits >= 64 {
+            u64::MAX
+        } else {
+            (1u64 << self.width_bits) - 1
+        }
+    }
+}
+
+/// Adds masked, read-modify-write access to [`SubRegister`] views on top of
+/// a `Core`'s raw `reg_read`/`reg_write`.
+pub trait SubRegisterAccess {
+    fn reg_read_sub(&mut self, sub: SubRegister) -> Result<u64>;
+    fn reg_write_sub(&mut self, sub: SubRegister, value: u64) -> Result<()>;
+}
+
+impl<'a, A: ArchT> SubRegisterAccess for Core<'a, A> {
+    fn reg_read_sub(&mut self, sub: SubRegister) -> Result<u64> {
+        let full = self.reg_read(sub.full_reg_id)?;
+        Ok(extract_sub(full, sub))
+    }
+
+    fn reg_write_sub(&mut self, sub: SubRegister, value: u64) -> Result<()> {
+        let full = self.reg_read(sub.full_reg_id)?;
+        self.reg_write(sub.full_reg_id, merge_sub(full, sub, value))
+    }
+}
+
+/// The masked, sign-extended read behind [`SubRegisterAccess::reg_read_sub`],
+/// split out so it can be unit tested without a live `Core`.
+fn extract_sub(full: u64, sub: SubRegister) -> u64 {
+    let mut value = (full >> sub.offset_bits) & sub.mask();
+    if sub.signed && sub.width_bits < 64 {
+        let sign_bit = 1u64 << (sub.width_bits - 1);
+        if value & sign_bit != 0 {
+            value |= !sub.mask();
+        }
+    }
+    value
+}
+
+/// The masked read-modify-write behind [`SubRegisterAccess::reg_write_sub`],
+/// split out so it can be unit tested without a live `Core`.
+fn merge_sub(full: u64, sub: SubRegister, value: u64) -> u64 {
+    let mask = sub.mask() << sub.offset_bits;
+    (full & !mask) | ((value << sub.offset_bits) & mask)
+}
+
+/// The 32-bit `wN` views of ARM64's `xN` registers, `W0` through `W30`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Arm64SubReg {
+    W0,
+    W1,
+    W2,
+    W3,
+    W4,
+    W5,
+    W6,
+    W7,
+    W8,
+    W9,
+    W10,
+    W11,
+    W12,
+    W13,
+    W14,
+    W15,
+    W16,
+    W17,
+    W18,
+    W19,
+    W20,
+    W21,
+    W22,
+    W23,
+    W24,
+    W25,
+    W26,
+    W27,
+    W28,
+    W29,
+    W30,
+}
+
+impl Arm64SubReg {
+    pub fn sub_register(self) -> SubRegister {
+        let full = match self {
+            Arm64SubReg::W0 => RegisterARM64::X0,
+            Arm64SubReg::W1 => RegisterARM64::X1,
+            Arm64SubReg::W2 => RegisterARM64::X2,
+            Arm64SubReg::W3 => RegisterARM64::X3,
+            Arm64SubReg::W4 => RegisterARM64::X4,
+            Arm64SubReg::W5 => RegisterARM64::X5,
+            Arm64SubReg::W6 => RegisterARM64::X6,
+            Arm64SubReg::W7 => RegisterARM64::X7,
+            Arm64SubReg::W8 => RegisterARM64::X8,
+            Arm64SubReg::W9 => RegisterARM64::X9,
+            Arm64SubReg::W10 => RegisterARM64::X10,
+            Arm64SubReg::W11 => RegisterARM64::X11,
+            Arm64SubReg::W12 => RegisterARM64::X12,
+            Arm64SubReg::W13 => RegisterARM64::X13,
+            Arm64SubReg::W14 => RegisterARM64::X14,
+            Arm64SubReg::W15 => RegisterARM64::X15,
+            Arm64SubReg::W16 => RegisterARM64::X16,
+            Arm64SubReg::W17 => RegisterARM64::X17,
+            Arm64SubReg::W18 => RegisterARM64::X18,
+            Arm64SubReg::W19 => RegisterARM64::X19,
+            Arm64SubReg::W20 => RegisterARM64::X20,
+            Arm64SubReg::W21 => RegisterARM64::X21,
+            Arm64SubReg::W22 => RegisterARM64::X22,
+            Arm64SubReg::W23 => RegisterARM64::X23,
+            Arm64SubReg::W24 => RegisterARM64::X24,
+            Arm64SubReg::W25 => RegisterARM64::X25,
+            Arm64SubReg::W26 => RegisterARM64::X26,
+            Arm64SubReg::W27 => RegisterARM64::X27,
+            Arm64SubReg::W28 => RegisterARM64::X28,
+            Arm64SubReg::W29 => RegisterARM64::X29,
+            Arm64SubReg::W30 => RegisterARM64::X30,
+        } as i32;
+        SubRegister::new(full, 0, 32)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extract_low_32_bits() {
+        let sub = SubRegister::new(0, 0, 32);
+        assert_eq!(extract_sub(0xdead_beef_0000_1234, sub), 0x0000_1234);
+    }
+
+    #[test]
+    fn extract_high_32_bits_via_offset() {
+        let sub = SubRegister::new(0, 32, 32);
+        assert_eq!(extract_sub(0xdead_beef_0000_1234, sub), 0xdead_beef);
+    }
+
+    #[test]
+    fn extract_sign_extends_negative_value() {
+        let sub = SubRegister::new(0, 0, 32).signed();
+        assert_eq!(extract_sub(0xffff_ffff_ffff_ffff, sub), u64::MAX);
+        assert_eq!(extract_sub(0x0000_0000_8000_0000, sub), 0xffff_ffff_8000_0000);
+    }
+
+    #[test]
+    fn extract_unsigned_does_not_sign_extend() {
+        let sub = SubRegister::new(0, 0, 32);
+        assert_eq!(extract_sub(0x0000_0000_8000_0000, sub), 0x8000_0000);
+    }
+
+    #[test]
+    fn merge_low_32_bits_preserves_upper_half() {
+        let sub = SubRegister::new(0, 0, 32);
+        let merged = merge_sub(0xdead_beef_0000_1234, sub, 0xffff_ffff);
+        assert_eq!(merged, 0xdead_beef_ffff_ffff);
+    }
+
+    #[test]
+    fn merge_high_32_bits_preserves_lower_half() {
+        let sub = SubRegister::new(0, 32, 32);
+        let merged = merge_sub(0xdead_beef_0000_1234, sub, 0x1111_2222);
+        assert_eq!(merged, 0x1111_2222_0000_1234);
+    }
+
+    #[test]
+    fn merge_truncates_value_wider_than_the_sub_register() {
+        let sub = SubRegister::new(0, 0, 32);
+        let merged = merge_sub(0, sub, 0xffff_ffff_0000_0001);
+        assert_eq!(merged, 0x0000_0001);
+    }
+
+    #[test]
+    fn arm64_w0_maps_to_x0_low_32_bits() {
+        let sub = Arm64SubReg::W0.sub_register();
+        assert_eq!(sub.full_reg_id, RegisterARM64::X0 as i32);
+        assert_eq!(sub.offset_bits, 0);
+        assert_eq!(sub.width_bits, 32);
+    }
+
+    #[test]
+    fn arm64_w30_maps_to_x30() {
+        let sub = Arm64SubReg::W30.sub_register();
+        assert_eq!(sub.full_reg_id, RegisterARM64::X30 as i32);
+    }
+}
@@ -0,0 +1,185 @@
+use crate::arch::ArchT;
+use crate::cc::CallingConvention;
+use crate::core::Core;
+use crate::errors::{EmulatorError, Result};
+use crate::memory::Memory;
+use bytes::{Buf, Bytes};
+use goblin::container::Endian;
+use std::collections::HashMap;
+use unicorn_engine::unicorn_const::HookType;
+
+pub type SyscallHandler<A> = Box<dyn FnMut(&mut Core<A>) -> Result<u64>>;
+
+/// Maps syscall numbers to handlers for a single `Core<A>` and installs the
+/// unicorn interrupt hook that routes trapped syscall instructions to them.
+pub struct SyscallManager<A: ArchT> {
+    handlers: HashMap<u64, SyscallHandler<A>>,
+}
+
+impl<A: ArchT> Default for SyscallManager<A> {
+    fn default() -> Self {
+        Self {
+            handlers: HashMap::new(),
+        }
+    }
+}
+
+impl<A: ArchT> SyscallManager<A> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn register(&mut self, number: u64, handler: SyscallHandler<A>) {
+        self.handlers.insert(number, handler);
+    }
+
+    /// Reads the syscall number off the register described by
+    /// `ArchT::syscall_number_reg_id`, invokes the matching handler (which
+    /// reads its own arguments, e.g. through [`syscall_arg`]), and writes its
+    /// result back into the return register.
+    pub fn dispatch(&mut self, core: &mut Core<A>) -> Result<()>
+    where
+        Core<A>: CallingConvention,
+    {
+        let number = core.reg_read(core.syscall_number_reg_id())?;
+        let result = match self.handlers.get_mut(&number) {
+            Some(handler) => handler(core)?,
+            None => return Err(EmulatorError::UnsupportedSyscall(number)),
+        };
+        core.set_return_value(result)
+    }
+}
+
+/// Reads the `idx`'th syscall argument off the registers described by
+/// `ArchT::syscall_arg_reg_ids`, spilling onto the stack past them — this is
+/// the syscall ABI, which is not always the same as the C calling
+/// convention `CallingConvention` models (e.g. x86-64 `syscall` reads its
+/// 4th argument from `r10`, where the C ABI would use `rcx`).
+pub fn syscall_arg<A: ArchT>(core: &mut Core<A>, idx: usize) -> Result<u64>
+where
+    Core<A>: Memory,
+{
+    let arg_regs = core.syscall_arg_reg_ids();
+    if let Some(&reg_id) = arg_regs.get(idx) {
+        return core.reg_read(reg_id);
+    }
+
+    let psize = core.pointer_size() as u64;
+    let sp = core.reg_read(core.sp_reg_id())?;
+    let addr = sp + (idx - arg_regs.len()) as u64 * psize;
+    let raw = core.mem_read_as_vec(addr, psize as usize)?;
+    let mut buf = Bytes::copy_from_slice(&raw);
+    Ok(match (core.endian(), psize) {
+        (Endian::Little, 4) => buf.get_u32_le() as u64,
+        (Endian::Big, 4) => buf.get_u32() as u64,
+        (Endian::Little, 8) => buf.get_u64_le(),
+        (Endian::Big, 8) => buf.get_u64(),
+        _ => return Err(EmulatorError::InvalidArg("unsupported pointer size".into())),
+    })
+}
+
+/// Installs an interrupt hook on `core` that dispatches every trapped
+/// syscall instruction through `manager`. Only the interrupt number reported
+/// by `ArchT::syscall_intno` is dispatched, which covers the backends in
+/// `arch` (MIPS `syscall`, AArch64 `svc`, RISC-V `ecall`); other interrupts
+/// are ignored, and x86 and 32-bit ARM syscall instructions are not handled.
+pub fn install_hook<A: ArchT + 'static>(
+    core: &mut Core<A>,
+    manager: std::rc::Rc<std::cell::RefCell<SyscallManager<A>>>,
+) -> Result<()>
+where
+    Core<A>: CallingConvention,
+{
+    core.add_hook(HookType::INTR, move |core, intno| {
+        if intno != core.syscall_intno() {
+            // Not the architecture's syscall-trapping interrupt; leave it alone.
+            return;
+        }
+        if let Err(e) = manager.borrow_mut().dispatch(core) {
+            log::warn!("unhandled syscall: {:?}", e);
+        }
+    })
+}
+
+/// Linux syscall numbers an `ArchT` needs to back [`register_default_handlers`].
+pub trait LinuxSyscallAbi: ArchT {
+    const SYS_BRK: u64;
+    const SYS_MMAP: u64;
+    const SYS_WRITE: u64;
+    const SYS_EXIT: u64;
+}
+
+/// A minimal brk/mmap/write/exit handler set, enough to run simple
+/// statically-linked programs to completion.
+pub fn register_default_handlers<A: LinuxSyscallAbi + 'static>(manager: &mut SyscallManager<A>)
+where
+    Core<A>: Memory,
+{
+    manager.register(
+        A::SYS_BRK,
+        Box::new(|core| {
+            let addr = syscall_arg(core, 0)?;
+            if addr == 0 {
+                core.get_data().brk()
+            } else {
+                core.set_brk(addr)
+            }
+        }),
+    );
+    manager.register(
+        A::SYS_MMAP,
+        Box::new(|core| {
+            let addr = syscall_arg(core, 0)?;
+            let len = syscall_arg(core, 1)?;
+            let prot = syscall_arg(core, 2)?;
+            core.mem_map_anonymous(addr, len as usize, prot as u32)
+        }),
+    );
+    manager.register(
+        A::SYS_WRITE,
+        Box::new(|core| {
+            let fd = syscall_arg(core, 0)?;
+            let buf = syscall_arg(core, 1)?;
+            let len = syscall_arg(core, 2)?;
+            let data = core.mem_read_as_vec(buf, len as usize)?;
+            use std::io::Write;
+            let written = match fd {
+                1 => std::io::stdout().write(&data).unwrap_or(0),
+                2 => std::io::stderr().write(&data).unwrap_or(0),
+                _ => 0,
+            };
+            Ok(written as u64)
+        }),
+    );
+    manager.register(
+        A::SYS_EXIT,
+        Box::new(|core| {
+            let code = syscall_arg(core, 0)?;
+            core.stop(code)?;
+            Ok(code)
+        }),
+    );
+}
+
+impl LinuxSyscallAbi for crate::arch::MIPS {
+    // o32 Linux syscalls are offset by 4000.
+    const SYS_BRK: u64 = 4045;
+    const SYS_MMAP: u64 = 4090;
+    const SYS_WRITE: u64 = 4004;
+    const SYS_EXIT: u64 = 4001;
+}
+
+impl LinuxSyscallAbi for crate::arch::ARM64 {
+    const SYS_BRK: u64 = 214;
+    const SYS_MMAP: u64 = 222;
+    const SYS_WRITE: u64 = 64;
+    const SYS_EXIT: u64 = 93;
+}
+
+impl LinuxSyscallAbi for crate::arch::RISCV64 {
+    // RISC-V reuses the generic Linux syscall table, same numbers as ARM64.
+    const SYS_BRK: u64 = 214;
+    const SYS_MMAP: u64 = 222;
+    const SYS_WRITE: u64 = 64;
+    const SYS_EXIT: u64 = 93;
+}
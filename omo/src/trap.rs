@@ -0,0 +1,131 @@
+use crate::arch::ArchT;
+use crate::core::Core;
+use crate::debugger::Debuggable;
+use crate::errors::Result;
+use std::collections::HashMap;
+use unicorn_engine::unicorn_const::{HookType, MemType};
+
+/// The class of event that tripped a unicorn hook, independent of the
+/// underlying `uc_error` used to report it today.
+///
+/// There is no `UnalignedAccess` variant: unicorn does not raise a distinct
+/// hook for misaligned accesses on the architectures this crate backs (MIPS,
+/// ARM64, RISC-V all either tolerate misalignment or fault through the
+/// regular `MEM_UNMAPPED`/`MEM_PROT` hooks), so a dedicated variant would be
+/// unreachable dead code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TrapKind {
+    MemUnmapped,
+    MemProt,
+    MemReadUnmapped,
+    MemWriteUnmapped,
+    MemFetchUnmapped,
+    MemReadProt,
+    MemWriteProt,
+    MemFetchProt,
+    InsnInvalid,
+    Intr,
+}
+
+/// What a trap handler wants to happen next.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrapAction {
+    /// Resume emulation from the current PC (the handler already fixed things
+    /// up, e.g. by mapping a page).
+    Resume,
+    /// Handled by the hooks [`install`] sets up exactly like `Resume`:
+    /// emulation continues, and PC is not advanced past the faulting
+    /// instruction or access.
+    Skip,
+    /// Halt emulation. For memory and invalid-instruction traps the caller
+    /// sees this as a hard error; for `Intr` emulation is ended with
+    /// `emu_stop`, which reports no error.
+    Stop,
+}
+
+pub type TrapHandler<A> = Box<dyn FnMut(&mut Core<A>, TrapKind, u64) -> TrapAction>;
+
+/// A registerable table of trap handlers, one per `TrapKind`, turning the
+/// all-or-nothing `EmulatorError` flow into something a caller can recover
+/// from by registering handlers for the traps it expects.
+#[derive(Default)]
+pub struct TrapTable<A: ArchT> {
+    handlers: HashMap<TrapKind, TrapHandler<A>>,
+}
+
+impl<A: ArchT> TrapTable<A> {
+    pub fn new() -> Self {
+        Self {
+            handlers: HashMap::new(),
+        }
+    }
+
+    pub fn register(&mut self, kind: TrapKind, handler: TrapHandler<A>) {
+        self.handlers.insert(kind, handler);
+    }
+
+    fn dispatch(&mut self, core: &mut Core<A>, kind: TrapKind, address: u64) -> TrapAction {
+        match self.handlers.get_mut(&kind) {
+            Some(handler) => handler(core, kind, address),
+            None => TrapAction::Stop,
+        }
+    }
+}
+
+/// Installs unicorn hooks for unmapped/protected memory accesses, invalid
+/// instructions and interrupts, routing each into `table` as a `TrapKind`.
+pub fn install<A: ArchT + 'static>(
+    core: &mut Core<A>,
+    table: std::rc::Rc<std::cell::RefCell<TrapTable<A>>>,
+) -> Result<()>
+where
+    Core<A>: Debuggable,
+{
+    let t = table.clone();
+    core.add_mem_hook(HookType::MEM_UNMAPPED, move |core, kind, address, _size, _value| {
+        let trap_kind = match kind {
+            MemType::READ_UNMAPPED => TrapKind::MemReadUnmapped,
+            MemType::WRITE_UNMAPPED => TrapKind::MemWriteUnmapped,
+            MemType::FETCH_UNMAPPED => TrapKind::MemFetchUnmapped,
+            _ => TrapKind::MemUnmapped,
+        };
+        match t.borrow_mut().dispatch(core, trap_kind, address) {
+            TrapAction::Stop => false,
+            _ => true,
+        }
+    })?;
+
+    let t = table.clone();
+    core.add_mem_hook(HookType::MEM_PROT, move |core, kind, address, _size, _value| {
+        let trap_kind = match kind {
+            MemType::READ_PROT => TrapKind::MemReadProt,
+            MemType::WRITE_PROT => TrapKind::MemWriteProt,
+            MemType::FETCH_PROT => TrapKind::MemFetchProt,
+            _ => TrapKind::MemProt,
+        };
+        match t.borrow_mut().dispatch(core, trap_kind, address) {
+            TrapAction::Stop => false,
+            _ => true,
+        }
+    })?;
+
+    let t = table.clone();
+    core.add_insn_invalid_hook(move |core| {
+        let pc = core.current_pc().unwrap_or_default();
+        match t.borrow_mut().dispatch(core, TrapKind::InsnInvalid, pc) {
+            TrapAction::Stop => false,
+            _ => true,
+        }
+    })?;
+
+    let t = table.clone();
+    core.add_hook(HookType::INTR, move |core, intno| {
+        let pc = core.current_pc().unwrap_or_default();
+        if let TrapAction::Stop = t.borrow_mut().dispatch(core, TrapKind::Intr, pc) {
+            let _ = core.emu_stop();
+        }
+        let _ = intno;
+    })?;
+
+    Ok(())
+}